@@ -0,0 +1,708 @@
+//! Source of libtls/libssl/libcrypto and logic to build them.
+//!
+//! This crate contains the sources of LibreSSL as a submodule and logic to
+//! build it. This is intended to be used by -sys crates as a build
+//! dependency so they don't have to probe the system for an installation.
+
+#![deny(warnings)]
+
+extern crate cc;
+extern crate cmake;
+extern crate pkg_config;
+#[cfg(feature = "bindgen")]
+extern crate bindgen;
+
+use std::collections::BTreeSet;
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// The LibreSSL release vendored by this crate, as a plain string (e.g.
+/// `"3.8.2"`).
+const LIBRESSL_VERSION: &str = "3.8.2";
+
+/// The thresholds at which a new `libresslMNF` cfg starts applying, ordered
+/// from oldest to newest. Mirrors the monotone-threshold structure
+/// `openssl-sys`'s `cfgs.rs` uses for `ossl*`/`libressl*` cfg emission.
+const LIBRESSL_CFG_THRESHOLDS: &[(u64, &str)] = &[
+    (0x2050_1000, "libressl251"),
+    (0x2050_2000, "libressl252"),
+    (0x2060_1000, "libressl261"),
+    (0x2070_0000, "libressl270"),
+    (0x2080_0000, "libressl280"),
+    (0x2090_1000, "libressl291"),
+    (0x3010_0000, "libressl310"),
+    (0x3020_1000, "libressl321"),
+    (0x3040_0000, "libressl340"),
+    (0x3050_0000, "libressl350"),
+    (0x3060_0000, "libressl360"),
+    (0x3080_2000, "libressl382"),
+];
+
+/// Packs a `major.minor.fix` release into the same `0xMNNFFPPS` form as
+/// `OPENSSL_VERSION_NUMBER`/`LIBRESSL_VERSION_NUMBER`, with `patch` and
+/// `status` left at zero.
+fn pack_version(major: u64, minor: u64, fix: u64) -> u64 {
+    (major << 28) | (minor << 20) | (fix << 12)
+}
+
+/// Parses a `major.minor.fix` string (e.g. `"3.8.2"`) into the packed form
+/// [`pack_version`] produces. Missing or non-numeric components are
+/// treated as `0`.
+fn parse_version(version: &str) -> u64 {
+    let mut parts = version.split('.').map(|part| part.parse().unwrap_or(0));
+    let major = parts.next().unwrap_or(0);
+    let minor = parts.next().unwrap_or(0);
+    let fix = parts.next().unwrap_or(0);
+    pack_version(major, minor, fix)
+}
+
+/// Returns the cfg names (excluding the always-on `libressl` flag) that
+/// apply to a given packed version number, in ascending order.
+fn cfgs_for_version(version: u64) -> Vec<&'static str> {
+    LIBRESSL_CFG_THRESHOLDS
+        .iter()
+        .filter(|&&(threshold, _)| version >= threshold)
+        .map(|&(_, name)| name)
+        .collect()
+}
+
+/// Maps a target triple to the `CMAKE_SYSTEM_NAME` CMake expects when
+/// cross-compiling.
+fn cmake_system_name(target: &str) -> Option<&'static str> {
+    if target.contains("android") {
+        Some("Android")
+    } else if target.contains("apple-ios") {
+        Some("iOS")
+    } else if target.contains("apple-darwin") {
+        Some("Darwin")
+    } else if target.contains("windows") {
+        Some("Windows")
+    } else if target.contains("linux") {
+        Some("Linux")
+    } else {
+        None
+    }
+}
+
+/// Maps a target triple to the `CMAKE_SYSTEM_PROCESSOR` CMake expects when
+/// cross-compiling.
+fn cmake_system_processor(target: &str) -> Option<&'static str> {
+    if target.contains("aarch64") {
+        Some("aarch64")
+    } else if target.contains("armv7") {
+        Some("armv7")
+    } else if target.contains("arm") {
+        Some("arm")
+    } else if target.contains("x86_64") {
+        Some("x86_64")
+    } else if target.starts_with("i686") || target.starts_with("i586") {
+        Some("i686")
+    } else {
+        None
+    }
+}
+
+/// Maps a target triple to the Android NDK `ANDROID_ABI` name.
+fn android_abi(target: &str) -> &'static str {
+    if target.contains("aarch64") {
+        "arm64-v8a"
+    } else if target.contains("armv7") {
+        "armeabi-v7a"
+    } else if target.contains("x86_64") {
+        "x86_64"
+    } else if target.contains("i686") {
+        "x86"
+    } else {
+        "armeabi-v7a"
+    }
+}
+
+/// Maps a target triple to the `CMAKE_OSX_ARCHITECTURES` name for iOS.
+fn osx_architecture(target: &str) -> &'static str {
+    if target.contains("aarch64") {
+        "arm64"
+    } else {
+        "x86_64"
+    }
+}
+
+/// Whether the target uses the MSVC toolchain, in which case LibreSSL's
+/// Unix perlasm output doesn't apply and nasm/yasm is used instead.
+fn target_env_is_msvc(target: &str) -> bool {
+    target.ends_with("-msvc")
+}
+
+/// Reads `LIBRESSL_VERSION_NUMBER` directly out of a system `opensslv.h`,
+/// for targets (like OpenBSD) where the installed version can't be
+/// obtained via `pkg-config`.
+fn read_system_version_number(include_dir: &Path) -> Option<u64> {
+    let header = std::fs::read_to_string(include_dir.join("openssl/opensslv.h")).ok()?;
+    header.lines().find_map(|line| {
+        let rest = line.trim().strip_prefix("#define")?.trim();
+        let rest = rest.strip_prefix("LIBRESSL_VERSION_NUMBER")?.trim();
+        let hex = rest.strip_prefix("0x")?;
+        let hex = hex.trim_end_matches(|c: char| !c.is_ascii_hexdigit());
+        u64::from_str_radix(hex, 16).ok()
+    })
+}
+
+/// Returns the version of LibreSSL vendored by this crate, e.g. `"3.8.2"`.
+pub fn version() -> &'static str {
+    LIBRESSL_VERSION
+}
+
+/// Returns the path to the vendored LibreSSL source tree.
+pub fn source_dir() -> &'static Path {
+    Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/libressl"))
+}
+
+/// A compile-time crypto trimming knob LibreSSL's CMake build supports,
+/// analogous to the `OPENSSL_NO_*` macros `openssl-sys` tracks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Feature {
+    /// Hand-written assembly routines (`no-asm`).
+    Asm,
+    /// The engine subsystem (`no-engine`).
+    Engine,
+    /// APIs marked deprecated by upstream (`no-deprecated`).
+    Deprecated,
+}
+
+impl Feature {
+    /// The CMake `-D` option that toggles this feature.
+    fn cmake_define(&self) -> &'static str {
+        match *self {
+            Feature::Asm => "ENABLE_ASM",
+            Feature::Engine => "ENABLE_ENGINE",
+            Feature::Deprecated => "ENABLE_DEPRECATED",
+        }
+    }
+
+    /// The `cargo:rustc-cfg` hint emitted when this feature is disabled.
+    fn cfg_name(&self) -> &'static str {
+        match *self {
+            Feature::Asm => "libressl_no_asm",
+            Feature::Engine => "libressl_no_engine",
+            Feature::Deprecated => "libressl_no_deprecated",
+        }
+    }
+}
+
+/// A builder for compiling the vendored LibreSSL source tree.
+pub struct Build {
+    out_dir: PathBuf,
+    target: Option<String>,
+    host: Option<String>,
+    build_libtls: bool,
+    shared: bool,
+    without: BTreeSet<Feature>,
+    extra_defines: Vec<String>,
+    generate_bindings: bool,
+    bindgen_allowlist: Vec<String>,
+    bindgen_blocklist: Vec<String>,
+}
+
+impl Default for Build {
+    fn default() -> Build {
+        Build::new()
+    }
+}
+
+impl Build {
+    /// Creates a new builder configured from the build script's environment
+    /// (`OUT_DIR`, `TARGET`, `HOST`).
+    pub fn new() -> Build {
+        Build {
+            out_dir: env::var_os("OUT_DIR")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| PathBuf::from("target/libressl-build")),
+            target: env::var("TARGET").ok(),
+            host: env::var("HOST").ok(),
+            build_libtls: true,
+            shared: false,
+            without: BTreeSet::new(),
+            extra_defines: Vec::new(),
+            generate_bindings: false,
+            bindgen_allowlist: Vec::new(),
+            bindgen_blocklist: Vec::new(),
+        }
+    }
+
+    /// Overrides the directory the build artifacts are placed in.
+    pub fn out_dir<P: AsRef<Path>>(&mut self, path: P) -> &mut Build {
+        self.out_dir = path.as_ref().to_path_buf();
+        self
+    }
+
+    /// Overrides the target triple to build for, which otherwise defaults
+    /// to the `TARGET` environment variable cargo sets for build scripts.
+    pub fn target(&mut self, target: &str) -> &mut Build {
+        self.target = Some(target.to_string());
+        self
+    }
+
+    /// Overrides the host triple the build runs on, which otherwise
+    /// defaults to the `HOST` environment variable cargo sets for build
+    /// scripts.
+    pub fn host(&mut self, host: &str) -> &mut Build {
+        self.host = Some(host.to_string());
+        self
+    }
+
+    /// Disables LibreSSL's hand-written assembly routines, falling back to
+    /// the portable C implementations. Needed for targets whose assembler
+    /// LibreSSL's perlasm doesn't support. Shorthand for
+    /// `without(Feature::Asm, no_asm)`.
+    pub fn no_asm(&mut self, no_asm: bool) -> &mut Build {
+        self.without(Feature::Asm, no_asm)
+    }
+
+    /// Enables or disables one of LibreSSL's compile-time trimming knobs.
+    pub fn without(&mut self, feature: Feature, disable: bool) -> &mut Build {
+        if disable {
+            self.without.insert(feature);
+        } else {
+            self.without.remove(&feature);
+        }
+        self
+    }
+
+    /// Passes a raw CMake `-D<NAME>=ON` define through to the LibreSSL
+    /// build, for knobs not covered by [`Feature`].
+    pub fn define(&mut self, name: &str) -> &mut Build {
+        self.extra_defines.push(name.to_string());
+        self
+    }
+
+    /// Whether to build libtls (the `tls_*` high-level API) in addition to
+    /// libssl/libcrypto. Enabled by default.
+    pub fn build_libtls(&mut self, enabled: bool) -> &mut Build {
+        self.build_libtls = enabled;
+        self
+    }
+
+    /// Whether to build `.so`/`.dylib`/`.dll` libraries instead of static
+    /// archives. Disabled by default: this crate exists so `-sys` crates
+    /// can link a vendored LibreSSL without probing the system, which only
+    /// works reliably with static linking.
+    pub fn shared(&mut self, enabled: bool) -> &mut Build {
+        self.shared = enabled;
+        self
+    }
+
+    /// Runs `bindgen` over the freshly built headers and writes the result
+    /// to `OUT_DIR/bindings.rs`, exposed via [`Artifacts::bindings_path`].
+    /// Disabled by default, and a no-op unless the `bindgen` crate feature
+    /// is enabled.
+    pub fn generate_bindings(&mut self, enabled: bool) -> &mut Build {
+        self.generate_bindings = enabled;
+        self
+    }
+
+    /// Adds a pattern to the allowlist passed to `bindgen`.
+    pub fn bindgen_allowlist_item(&mut self, pattern: &str) -> &mut Build {
+        self.bindgen_allowlist.push(pattern.to_string());
+        self
+    }
+
+    /// Adds a pattern to the blocklist passed to `bindgen`.
+    pub fn bindgen_blocklist_item(&mut self, pattern: &str) -> &mut Build {
+        self.bindgen_blocklist.push(pattern.to_string());
+        self
+    }
+
+    /// Builds libssl and libcrypto (and, unless disabled, libtls) from the
+    /// vendored source tree, returning their install locations.
+    ///
+    /// Honors `OPENSSL_NO_VENDOR`: if it is set to anything other than `0`,
+    /// a system installation is used instead (see [`Build::try_system`]),
+    /// falling back to the vendored build if none is found.
+    pub fn build(&mut self) -> Artifacts {
+        if self.wants_system() {
+            if let Some(artifacts) = self.try_system() {
+                return artifacts;
+            }
+        }
+
+        let target = self.target.clone().unwrap_or_else(|| {
+            env::var("TARGET").expect("TARGET environment variable not set")
+        });
+        let host = self
+            .host
+            .clone()
+            .unwrap_or_else(|| env::var("HOST").unwrap_or_else(|_| target.clone()));
+
+        let mut config = cmake::Config::new(source_dir());
+        config
+            .define("LIBRESSL_APPS", "OFF")
+            .define("LIBRESSL_TESTS", "OFF")
+            .define(
+                "ENABLE_LIBTLS",
+                if self.build_libtls { "ON" } else { "OFF" },
+            )
+            .target(&target)
+            .host(&host)
+            .out_dir(&self.out_dir);
+
+        config.define("BUILD_SHARED_LIBS", if self.shared { "ON" } else { "OFF" });
+
+        for feature in &self.without {
+            config.define(feature.cmake_define(), "OFF");
+        }
+        for name in &self.extra_defines {
+            config.define(name, "ON");
+        }
+
+        if target != host {
+            let compiler = cc::Build::new().target(&target).host(&host).get_compiler();
+            config.define("CMAKE_C_COMPILER", compiler.path());
+
+            if let Some(name) = cmake_system_name(&target) {
+                config.define("CMAKE_SYSTEM_NAME", name);
+            }
+            if let Some(processor) = cmake_system_processor(&target) {
+                config.define("CMAKE_SYSTEM_PROCESSOR", processor);
+            }
+
+            if target.contains("android") {
+                config.define("ANDROID_ABI", android_abi(&target));
+            } else if target.contains("apple-ios") {
+                config.define("CMAKE_OSX_ARCHITECTURES", osx_architecture(&target));
+            }
+        }
+
+        if !target_env_is_msvc(&target) {
+            if let Ok(ar) = cc::Build::new()
+                .target(&target)
+                .host(&host)
+                .get_archiver()
+                .get_program()
+                .to_owned()
+                .into_string()
+            {
+                config.define("CMAKE_AR", ar);
+            }
+        }
+
+        let disabled_cfgs: Vec<String> = self
+            .without
+            .iter()
+            .map(|feature| feature.cfg_name().to_string())
+            .collect();
+
+        let install_dir = config.build();
+
+        let lib_dir = if install_dir.join("lib64").exists() {
+            install_dir.join("lib64")
+        } else {
+            install_dir.join("lib")
+        };
+
+        // libtls depends on both libssl and libcrypto, so it must be listed
+        // first: static linkers resolve undefined symbols by searching the
+        // libraries that come after the one that references them.
+        let mut libs = Vec::new();
+        if self.build_libtls {
+            libs.push("tls".to_string());
+        }
+        libs.push("ssl".to_string());
+        libs.push("crypto".to_string());
+
+        let include_dir = install_dir.join("include");
+        let bindings_path = if self.generate_bindings {
+            self.run_bindgen(&target, &include_dir)
+        } else {
+            None
+        };
+
+        Artifacts {
+            lib_dir,
+            bin_dir: install_dir.join("bin"),
+            include_dir,
+            libs,
+            target,
+            version_number: parse_version(LIBRESSL_VERSION),
+            system: false,
+            shared: self.shared,
+            disabled_cfgs,
+            bindings_path,
+        }
+    }
+
+    /// Runs `bindgen` against `include_dir`'s headers, writing the result
+    /// into `OUT_DIR/bindings.rs`. Returns `None` if the `bindgen` crate
+    /// feature isn't enabled.
+    #[cfg(feature = "bindgen")]
+    fn run_bindgen(&self, target: &str, include_dir: &Path) -> Option<PathBuf> {
+        let mut builder = bindgen::Builder::default()
+            .header(include_dir.join("openssl/opensslv.h").to_str()?.to_string())
+            .header(include_dir.join("openssl/crypto.h").to_str()?.to_string())
+            .header(include_dir.join("openssl/ssl.h").to_str()?.to_string())
+            .clang_arg(format!("-I{}", include_dir.display()))
+            .clang_arg(format!("--target={}", target));
+
+        if self.build_libtls {
+            builder = builder.header(include_dir.join("tls.h").to_str()?.to_string());
+        }
+
+        for pattern in &self.bindgen_allowlist {
+            builder = builder.allowlist_item(pattern);
+        }
+        for pattern in &self.bindgen_blocklist {
+            builder = builder.blocklist_item(pattern);
+        }
+
+        let bindings = builder.generate().ok()?;
+        let out_path = self.out_dir.join("bindings.rs");
+        bindings.write_to_file(&out_path).ok()?;
+        Some(out_path)
+    }
+
+    #[cfg(not(feature = "bindgen"))]
+    fn run_bindgen(&self, _target: &str, _include_dir: &Path) -> Option<PathBuf> {
+        None
+    }
+
+    /// Returns `true` if `OPENSSL_NO_VENDOR` is set to anything other than
+    /// `0`, i.e. the caller asked to skip the vendored build.
+    fn wants_system(&self) -> bool {
+        match env::var("OPENSSL_NO_VENDOR") {
+            Ok(ref val) if val == "0" => false,
+            Ok(_) => true,
+            Err(_) => false,
+        }
+    }
+
+    /// Looks for a system installation of libtls/libssl/libcrypto via
+    /// `pkg-config`, returning `None` if one can't be found.
+    ///
+    /// OpenBSD doesn't ship a `libtls.pc`, so on that target the headers
+    /// and libraries are assumed to live under `/usr/include`/`/usr/lib`
+    /// instead of being probed.
+    pub fn try_system(&self) -> Option<Artifacts> {
+        let target = self
+            .target
+            .clone()
+            .unwrap_or_else(|| env::var("TARGET").unwrap_or_default());
+
+        if target.contains("openbsd") {
+            let mut libs = Vec::new();
+            if self.build_libtls {
+                libs.push("tls".to_string());
+            }
+            libs.push("ssl".to_string());
+            libs.push("crypto".to_string());
+
+            return Some(Artifacts {
+                lib_dir: PathBuf::from("/usr/lib"),
+                bin_dir: PathBuf::from("/usr/bin"),
+                include_dir: PathBuf::from("/usr/include"),
+                libs,
+                target,
+                version_number: read_system_version_number(Path::new("/usr/include"))
+                    .unwrap_or_else(|| parse_version(LIBRESSL_VERSION)),
+                system: true,
+                shared: true,
+                disabled_cfgs: Vec::new(),
+                bindings_path: None,
+            });
+        }
+
+        let mut names = Vec::new();
+        if self.build_libtls {
+            names.push("libtls");
+        }
+        names.push("libssl");
+        names.push("libcrypto");
+
+        let mut include_dir = None;
+        let mut lib_dir = None;
+        let mut version = None;
+        let mut libs = Vec::new();
+        for name in names {
+            let library = pkg_config::Config::new().cargo_metadata(false).probe(name).ok()?;
+            if include_dir.is_none() {
+                include_dir = library.include_paths.into_iter().next();
+            }
+            if lib_dir.is_none() {
+                lib_dir = library.link_paths.into_iter().next();
+            }
+            if version.is_none() && !library.version.is_empty() {
+                version = Some(library.version.clone());
+            }
+            libs.push(name.trim_start_matches("lib").to_string());
+        }
+
+        let version_number = version
+            .map(|version| parse_version(&version))
+            .or_else(|| include_dir.as_deref().and_then(read_system_version_number))
+            .unwrap_or_else(|| parse_version(LIBRESSL_VERSION));
+
+        Some(Artifacts {
+            lib_dir: lib_dir?,
+            bin_dir: PathBuf::new(),
+            include_dir: include_dir?,
+            libs,
+            target,
+            version_number,
+            system: true,
+            shared: true,
+            disabled_cfgs: Vec::new(),
+            bindings_path: None,
+        })
+    }
+}
+
+/// The result of a [`Build::build`] call: where the compiled libraries and
+/// headers ended up, and how to tell cargo about them.
+pub struct Artifacts {
+    lib_dir: PathBuf,
+    bin_dir: PathBuf,
+    include_dir: PathBuf,
+    libs: Vec<String>,
+    target: String,
+    version_number: u64,
+    system: bool,
+    shared: bool,
+    disabled_cfgs: Vec<String>,
+    bindings_path: Option<PathBuf>,
+}
+
+impl Artifacts {
+    /// The directory containing the installed headers.
+    pub fn include_dir(&self) -> &Path {
+        &self.include_dir
+    }
+
+    /// The directory containing the installed libraries.
+    pub fn lib_dir(&self) -> &Path {
+        &self.lib_dir
+    }
+
+    /// The directory containing installed binaries, if any were built.
+    pub fn bin_dir(&self) -> &Path {
+        &self.bin_dir
+    }
+
+    /// The names of the libraries that were built, in link order.
+    pub fn libs(&self) -> &[String] {
+        &self.libs
+    }
+
+    /// The target triple these artifacts were built for.
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// The LibreSSL version these artifacts belong to (the vendored release,
+    /// or the installed one for a system installation), packed the same way
+    /// `LIBRESSL_VERSION_NUMBER` is in `opensslv.h` (e.g. `0x3080_2000`).
+    pub fn version_number(&self) -> u64 {
+        self.version_number
+    }
+
+    /// The path to the generated `bindings.rs`, if
+    /// [`Build::generate_bindings`] was enabled.
+    pub fn bindings_path(&self) -> Option<&Path> {
+        self.bindings_path.as_deref()
+    }
+
+    /// Prints the `cargo:` directives that tell cargo (and downstream
+    /// `-sys` crates) where these artifacts live.
+    pub fn print_cargo_metadata(&self) {
+        println!("cargo:rustc-link-search=native={}", self.lib_dir.display());
+        for lib in &self.libs {
+            if self.system {
+                println!("cargo:rustc-link-lib={}", lib);
+            } else if self.shared {
+                println!("cargo:rustc-link-lib=dylib={}", lib);
+            } else {
+                println!("cargo:rustc-link-lib=static={}", lib);
+            }
+        }
+        println!("cargo:include={}", self.include_dir.display());
+        println!("cargo:lib={}", self.lib_dir.display());
+
+        println!("cargo:rustc-cfg=libressl");
+        for cfg in cfgs_for_version(self.version_number) {
+            println!("cargo:rustc-cfg={}", cfg);
+        }
+        for cfg in &self.disabled_cfgs {
+            println!("cargo:rustc-cfg={}", cfg);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pack_version_matches_libressl_version_number_layout() {
+        assert_eq!(pack_version(3, 8, 2), 0x3080_2000);
+        assert_eq!(pack_version(2, 5, 1), 0x2050_1000);
+    }
+
+    #[test]
+    fn parse_version_packs_a_dotted_version_string() {
+        assert_eq!(parse_version("3.8.2"), pack_version(3, 8, 2));
+        assert_eq!(parse_version("2.5.1"), pack_version(2, 5, 1));
+    }
+
+    #[test]
+    fn parse_version_treats_missing_or_non_numeric_parts_as_zero() {
+        assert_eq!(parse_version("3.8"), pack_version(3, 8, 0));
+        assert_eq!(parse_version(""), pack_version(0, 0, 0));
+        assert_eq!(parse_version("x.y.z"), pack_version(0, 0, 0));
+    }
+
+    #[test]
+    fn cfgs_for_version_is_empty_below_the_oldest_threshold() {
+        assert!(cfgs_for_version(0x2050_1000 - 1).is_empty());
+    }
+
+    #[test]
+    fn cfgs_for_version_includes_thresholds_at_and_below_the_version() {
+        let cfgs = cfgs_for_version(0x2050_1000);
+        assert_eq!(cfgs, vec!["libressl251"]);
+
+        let cfgs = cfgs_for_version(0x3080_2000);
+        assert_eq!(cfgs.first(), Some(&"libressl251"));
+        assert_eq!(cfgs.last(), Some(&"libressl382"));
+    }
+
+    #[test]
+    fn cmake_system_name_covers_common_triples() {
+        assert_eq!(cmake_system_name("aarch64-linux-android"), Some("Android"));
+        assert_eq!(cmake_system_name("x86_64-apple-ios"), Some("iOS"));
+        assert_eq!(cmake_system_name("x86_64-pc-windows-msvc"), Some("Windows"));
+        assert_eq!(cmake_system_name("x86_64-unknown-linux-gnu"), Some("Linux"));
+    }
+
+    #[test]
+    fn cmake_system_processor_covers_common_triples() {
+        assert_eq!(cmake_system_processor("aarch64-linux-android"), Some("aarch64"));
+        assert_eq!(cmake_system_processor("x86_64-apple-ios"), Some("x86_64"));
+        assert_eq!(cmake_system_processor("i686-pc-windows-msvc"), Some("i686"));
+    }
+
+    #[test]
+    fn android_abi_covers_common_triples() {
+        assert_eq!(android_abi("aarch64-linux-android"), "arm64-v8a");
+        assert_eq!(android_abi("x86_64-linux-android"), "x86_64");
+        assert_eq!(android_abi("i686-linux-android"), "x86");
+    }
+
+    #[test]
+    fn osx_architecture_covers_common_triples() {
+        assert_eq!(osx_architecture("aarch64-apple-ios"), "arm64");
+        assert_eq!(osx_architecture("x86_64-apple-ios"), "x86_64");
+    }
+
+    #[test]
+    fn target_env_is_msvc_detects_msvc_triples() {
+        assert!(target_env_is_msvc("x86_64-pc-windows-msvc"));
+        assert!(!target_env_is_msvc("x86_64-pc-windows-gnu"));
+        assert!(!target_env_is_msvc("x86_64-unknown-linux-gnu"));
+    }
+}